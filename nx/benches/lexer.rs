@@ -7,7 +7,7 @@ fn lexer(c: &mut Criterion) {
 
     fn tokenize_string(code: &str) {
         let code = black_box(code);
-        let _ = lex::tokenize_string(&code).unwrap();
+        let (_tokens, _diagnostics) = lex::tokenize_string(code);
     }
 
     c.bench_function("lexer_tokenize_string", |b| {
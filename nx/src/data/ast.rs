@@ -21,6 +21,13 @@ pub enum AstNode {
     String(AstNodeData),
     Identifier(AstNodeData),
 
+    // Operators
+    //
+    // The operator's own `SourceSpan` identifies which operator it is,
+    // since the operand(s) are the node(s) immediately preceding it.
+    BinaryOp(AstNodeData),
+    UnaryOp(AstNodeData),
+
     // Helpers
     StartFunction(AstNodeData),
     EndFunction(AstNodeData),
@@ -0,0 +1,159 @@
+use crate::lexer::token::SourceSpan;
+
+/// Severity of a [`Diagnostic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A single lexer or parser problem, carrying enough information to be
+/// rendered against the original source.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: SourceSpan,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>, span: SourceSpan) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message: message.into(),
+            span,
+        }
+    }
+
+    pub fn warning(message: impl Into<String>, span: SourceSpan) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            message: message.into(),
+            span,
+        }
+    }
+
+    /// Render this diagnostic against the original `source`, mirroring
+    /// how established Rust parsers surface an error: a `line:column`
+    /// location, followed by the offending line and a caret underline
+    /// spanning `span.start..span.end`.
+    pub fn render(&self, source: &str) -> String {
+        let (line, column, line_start) = locate(source, self.span.start as usize);
+        let line_text = source[line_start..].lines().next().unwrap_or("");
+        // `column` is already a 1-indexed char count from `line_start`,
+        // so reuse it instead of re-deriving the offset via byte math
+        // (which would overcount for any multi-byte character on the
+        // line before the span).
+        let underline_start = column - 1;
+        let underline_len = (self.span.end - self.span.start).max(1) as usize;
+
+        format!(
+            "{}:{}: {}\n{}\n{}{}",
+            line,
+            column,
+            self.message,
+            line_text,
+            " ".repeat(underline_start),
+            "^".repeat(underline_len),
+        )
+    }
+}
+
+/// Collects [`Diagnostic`]s produced while lexing or parsing, so a run
+/// can report every problem it finds instead of aborting on the first.
+#[derive(Debug, Default, Clone)]
+pub struct Diagnostics {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    pub fn extend(&mut self, diagnostics: impl IntoIterator<Item = Diagnostic>) {
+        self.diagnostics.extend(diagnostics);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    /// Render every diagnostic against `source`, separated by a blank
+    /// line, in the order they were recorded.
+    pub fn render(&self, source: &str) -> String {
+        self.diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.render(source))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// Find the 1-indexed `(line, column)` of `byte_offset` in `source`,
+/// along with the byte offset the line itself starts at.
+fn locate(source: &str, byte_offset: usize) -> (usize, usize, usize) {
+    let mut line = 1;
+    let mut column = 1;
+    let mut line_start = 0;
+    for (index, c) in source.char_indices() {
+        if index >= byte_offset {
+            break;
+        }
+        if c == '\n' {
+            line += 1;
+            column = 1;
+            line_start = index + 1;
+        } else {
+            column += 1;
+        }
+    }
+    (line, column, line_start)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_points_at_span() {
+        let source = "fn main() {\n    1 + ;\n}";
+        let diagnostic = Diagnostic::error("expected an expression", SourceSpan::new(20, 21));
+        let rendered = diagnostic.render(source);
+        assert!(rendered.starts_with("2:9: expected an expression"));
+        assert!(rendered.contains("    1 + ;"));
+        assert!(rendered.contains('^'));
+    }
+
+    #[test]
+    fn test_render_counts_characters_not_bytes_for_column() {
+        // The fullwidth semicolon before it is 3 bytes but 1 character,
+        // so a byte-based column would overshoot by 2.
+        let source = "let x = 0\u{FF1B} let y = x \u{2212} 1;";
+        let minus_byte_offset = source.find('\u{2212}').unwrap();
+        let diagnostic = Diagnostic::error(
+            "found a Unicode minus sign",
+            SourceSpan::new(minus_byte_offset, minus_byte_offset + 1),
+        );
+        let rendered = diagnostic.render(source);
+        assert!(rendered.starts_with("1:22: found a Unicode minus sign"));
+    }
+}
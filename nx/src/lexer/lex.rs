@@ -1,9 +1,202 @@
-use crate::error::{ensure, Result};
+use crate::error::{bail, ensure, Result};
 //
+use crate::diagnostic::{Diagnostic, Diagnostics};
 use crate::lexer::token::{SourceSpan, Token};
 
+/// Resource limits enforced while lexing a single source string.
+///
+/// These bound how much work and memory a [`Lexer`] may consume, and
+/// are set explicitly by whoever constructs the [`Lexer`] or
+/// [`TokenCursor`]. [`tokenize_string_standard`] takes no limits: it
+/// caps the token count at five times its own per-line estimate.
+#[derive(Debug, Clone, Copy)]
+pub struct LexerLimits {
+    pub max_num_tokens: usize,
+    pub max_identifier_length: usize,
+}
+
+impl Default for LexerLimits {
+    fn default() -> Self {
+        LexerLimits {
+            max_num_tokens: usize::MAX,
+            max_identifier_length: usize::MAX,
+        }
+    }
+}
+
+/// Pull-based tokenizer that yields one [`Token`] at a time.
+///
+/// Unlike [`tokenize_string_standard`], this does not materialize the
+/// whole token stream up front, so a caller that only needs a small
+/// amount of lookahead does not have to pay for a `Vec<Token>` it never
+/// fully needs. [`TokenCursor`] wraps a `Lexer` to give the parser
+/// exactly that: lazy consumption with a one-token peek buffer.
+///
+/// Once a call to `next` returns `Some(Err(_))`, the lexer is spent and
+/// every subsequent call returns `None`. [`TokenCursor`] does not use
+/// this `Iterator` impl for that reason — it calls the inherent
+/// `advance` method directly so it can recover from an error and keep
+/// going instead of stopping dead.
+pub struct Lexer<'code> {
+    code: &'code str,
+    code_index: usize,
+    limits: LexerLimits,
+    num_tokens: usize,
+    warnings: Vec<Diagnostic>,
+}
+
+impl<'code> Lexer<'code> {
+    pub fn new(code: &'code str, limits: LexerLimits) -> Self {
+        Lexer {
+            code,
+            code_index: 0,
+            limits,
+            num_tokens: 0,
+            warnings: vec![],
+        }
+    }
+
+    /// Diagnostics recorded for tokens that were recovered rather than
+    /// rejected outright (currently: a Unicode delimiter confusable
+    /// substituted for its ASCII lookalike). Draining leaves the lexer's
+    /// own list empty, so callers that poll after every token never see
+    /// the same warning twice.
+    pub fn take_warnings(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.warnings)
+    }
+}
+
+impl<'code> Iterator for Lexer<'code> {
+    type Item = Result<Token<'code>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.code.is_empty() {
+            return None;
+        }
+
+        let result = self.advance();
+        if result.is_err() {
+            // Stop iterating once an error has been reported.
+            self.code = "";
+        }
+        Some(result)
+    }
+}
+
+impl<'code> Lexer<'code> {
+    fn advance(&mut self) -> Result<Token<'code>> {
+        if self.num_tokens >= self.limits.max_num_tokens {
+            bail!("too many tokens were found during lexing");
+        }
+
+        let (token, remaining_code, new_code_index, warning) =
+            tokenize_next_word(self.code, self.code_index)?;
+        ensure!(!token.is_none(), "Token::None was found during lexing.");
+        if let Some((_, word)) = token.as_idx_val() {
+            ensure!(
+                word.len() <= self.limits.max_identifier_length,
+                "identifier is longer than max_identifier_length"
+            );
+        }
+        if let Some(warning) = warning {
+            self.warnings.push(warning);
+        }
+
+        self.code = remaining_code;
+        self.code_index = new_code_index;
+        self.num_tokens += 1;
+
+        Ok(token)
+    }
+}
+
+/// Forward-only cursor over a [`Lexer`] with a one-token lookahead
+/// buffer, so a caller like the parser can look at the next token
+/// without materializing the whole stream as a `Vec<Token>`.
+///
+/// Lexing errors are recovered the same way [`tokenize_string_standard`]
+/// recovers: skip to the next statement boundary and keep going,
+/// recording a [`Diagnostic`] for each one encountered along the way.
+pub struct TokenCursor<'code> {
+    lexer: Lexer<'code>,
+    peeked: Option<Token<'code>>,
+    diagnostics: Diagnostics,
+}
+
+impl<'code> TokenCursor<'code> {
+    pub fn new(code: &'code str, limits: LexerLimits) -> Self {
+        TokenCursor {
+            lexer: Lexer::new(code, limits),
+            peeked: None,
+            diagnostics: Diagnostics::new(),
+        }
+    }
+
+    /// The next token to be returned by [`Self::advance`], without
+    /// consuming it.
+    pub fn peek(&mut self) -> Option<&Token<'code>> {
+        self.fill();
+        self.peeked.as_ref()
+    }
+
+    /// Consume and return the peeked token, pulling a fresh one in to
+    /// replace it.
+    pub fn advance(&mut self) -> Option<Token<'code>> {
+        self.fill();
+        self.peeked.take()
+    }
+
+    /// Diagnostics recorded while lexing so far (errors and recovered
+    /// confusable-delimiter warnings). Draining leaves the cursor's own
+    /// list empty, so callers that poll after every token never see the
+    /// same diagnostic twice.
+    pub fn take_diagnostics(&mut self) -> Diagnostics {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    /// Pull tokens from the lexer, recovering from errors, until either
+    /// a token is buffered or the source is exhausted.
+    fn fill(&mut self) {
+        while self.peeked.is_none() && !self.lexer.code.is_empty() {
+            if self.lexer.num_tokens >= self.lexer.limits.max_num_tokens {
+                let error_span = SourceSpan::new(self.lexer.code_index, self.lexer.code_index);
+                self.diagnostics.push(Diagnostic::error(
+                    "too many tokens were found during lexing",
+                    error_span,
+                ));
+                self.lexer.code = "";
+                break;
+            }
+
+            match self.lexer.advance() {
+                Ok(token) => {
+                    self.diagnostics.extend(self.lexer.take_warnings());
+                    self.peeked = Some(token);
+                }
+                Err(error) => {
+                    let error_index = self.lexer.code_index;
+                    let error_span = SourceSpan::new(error_index, error_index);
+                    self.diagnostics
+                        .push(Diagnostic::error(error.to_string(), error_span));
+                    // Recover by skipping to the next statement boundary
+                    // and resuming lexing from there.
+                    let (recovered_code, recovered_index) =
+                        recover_to_statement_boundary(self.lexer.code, error_index);
+                    self.lexer.code = recovered_code;
+                    self.lexer.code_index = recovered_index;
+                }
+            }
+        }
+    }
+}
+
 /// Tokenize code.
 ///
+/// Never aborts on the first problem: any lexing error is recorded as
+/// a [`Diagnostic`] and lexing resumes at the next statement boundary,
+/// so the caller gets both the tokens that could be recovered and the
+/// full list of problems found.
+///
 /// # Performance Consideration
 ///
 /// A Vec<Token> will be created to return the result.
@@ -12,12 +205,14 @@ use crate::lexer::token::{SourceSpan, Token};
 ///
 /// This can be slow if used in performance critical code.
 #[allow(clippy::needless_lifetimes)]
-pub fn tokenize_string<'code>(code: &'code str) -> Result<Vec<Token<'code>>> {
+pub fn tokenize_string<'code>(code: &'code str) -> (Vec<Token<'code>>, Diagnostics) {
     tokenize_string_standard(code, vec![])
 }
 
 /// Tokenize code.
 ///
+/// See [`tokenize_string`] for the error-recovery behavior.
+///
 /// # Performance Consideration
 ///
 /// A Vec<Token> is required as an argument to return the result.
@@ -28,44 +223,51 @@ pub fn tokenize_string<'code>(code: &'code str) -> Result<Vec<Token<'code>>> {
 ///
 /// This can be used in performance critical code.
 pub fn tokenize_string_standard<'code>(
-    mut code: &'code str,
+    code: &'code str,
     mut tokens: Vec<Token<'code>>,
-) -> Result<Vec<Token<'code>>> {
-    ensure!(!code.is_empty(), "code can not be empty string");
+) -> (Vec<Token<'code>>, Diagnostics) {
     tokens.clear();
 
+    if code.is_empty() {
+        let mut diagnostics = Diagnostics::new();
+        diagnostics.push(Diagnostic::error(
+            "code can not be empty string",
+            SourceSpan::default(),
+        ));
+        return (tokens, diagnostics);
+    }
+
     // Allocate enough memory for tokens
     let num_lines = code.chars().filter(|&c| c == '\n').count();
     let guess_num_tokens_per_line = 20;
     let guess_num_tokens = (num_lines + 1) * guess_num_tokens_per_line;
-    let max_num_tokens = guess_num_tokens * 5;
+    let limits = LexerLimits {
+        max_num_tokens: guess_num_tokens * 5,
+        ..LexerLimits::default()
+    };
     //
     if tokens.capacity() < guess_num_tokens {
         tokens.reserve(guess_num_tokens - tokens.capacity());
     }
 
-    let mut code_index = 0;
-
-    // Tokenize one by one until the code is not empty
-    while !code.is_empty() {
-        // Find next token
-        let (token, remaining_code, new_code_index) = tokenize_next_word(code, code_index)?;
-        // Check if token is identified corrrectly
-        ensure!(!token.is_none(), "Token::None was found during lexing.");
-        // Check for memory overflow
-        ensure!(
-            tokens.len() < max_num_tokens,
-            "can not store more than max_num_tokens as it may cause memory overflow"
-        );
-        // Store token
+    let mut cursor = TokenCursor::new(code, limits);
+    while let Some(token) = cursor.advance() {
         tokens.push(token);
-        // Truncate code to process rest of the remaining code
-        // Warning: Without this the loop will run forever
-        code = remaining_code;
-        code_index = new_code_index;
     }
 
-    Ok(tokens)
+    (tokens, cursor.take_diagnostics())
+}
+
+/// Recover from a lexing error by skipping past the next `;` or `\n`
+/// (or to the end of input if neither appears), so lexing can resume
+/// at the next statement.
+fn recover_to_statement_boundary(code: &str, code_index: usize) -> (&str, usize) {
+    let boundary = code
+        .char_indices()
+        .find(|&(_, c)| c == ';' || c == '\n')
+        .map(|(index, c)| index + c.len_utf8())
+        .unwrap_or(code.len());
+    (&code[boundary..], code_index + boundary)
 }
 
 /// Find the next token.
@@ -79,43 +281,67 @@ pub fn tokenize_string_standard<'code>(
 /// + Match the word in two steps as,
 ///   - Check if the word is a reserved token (use peek).
 ///   - Check if the word is a float, integer, string, or identifier.
-pub fn tokenize_next_word(code: &str, code_index: usize) -> Result<(Token, &str, usize)> {
+pub fn tokenize_next_word(
+    code: &str,
+    code_index: usize,
+) -> Result<(Token<'_>, &str, usize, Option<Diagnostic>)> {
     let (code, code_index) = truncate_leading_whitespace(code, code_index);
 
+    // String literals are special-cased up front since the closing `"`
+    // and any escaped characters inside it must not be split into words
+    // by `search_next_word`.
+    if code.starts_with('"') {
+        let (token, remaining_code, new_index) = tokenize_string_literal(code, code_index)?;
+        return Ok((token, remaining_code, new_index, None));
+    }
+
     let (word, remaining_code, new_index) = search_next_word(code, code_index);
     let span = SourceSpan::new(code_index, code_index + word.len());
 
-    // Tokenize documentation comments
-    if word == "/" {
-        let (next_word_1, remaining_code, new_index) = search_next_word(remaining_code, new_index);
-        let (next_word_2, remaining_code, _________) = search_next_word(remaining_code, new_index);
-        if next_word_1 == "/" && next_word_2 == "/" {
-            let next_newline_index = remaining_code
-                .chars()
-                .position(|c| c == '\n')
-                .unwrap_or(remaining_code.len());
-            let remaining_truncated_code = &remaining_code[next_newline_index..];
-            let comment = &code[0..(next_newline_index + 3)];
-            let span = SourceSpan::new(code_index, code_index + comment.len());
-            let token = Token::Documentation(span, comment);
-            return Ok((token, remaining_truncated_code, span.end as usize));
+    // Tokenize float literals
+    //
+    // A run of digits immediately followed by `.` and more digits is a
+    // float (e.g. `123.45`, `1_000.5`) rather than an integer followed
+    // by a `Dot`, so this must be checked before `.` is classified.
+    if !word.is_empty() && word.chars().all(character_is_integer) {
+        if let Some(after_dot) = remaining_code.strip_prefix('.') {
+            if after_dot.chars().next().is_some_and(character_is_integer) {
+                let (fraction, remaining_code, _) = search_next_word(after_dot, new_index + 1);
+                if fraction.chars().all(character_is_integer) {
+                    let literal = &code[0..(word.len() + 1 + fraction.len())];
+                    let span = SourceSpan::new(code_index, code_index + literal.len());
+                    let token = Token::FltVal(span, literal);
+                    return Ok((token, remaining_code, span.end as usize, None));
+                }
+            }
         }
     }
 
+    // Tokenize documentation comments
+    //
+    // `search_next_word` already performs maximal munch, so `word` is
+    // the whole `///` marker rather than three single-char peeks.
+    if word == "///" {
+        let next_newline_index = remaining_code
+            .find('\n')
+            .unwrap_or(remaining_code.len());
+        let remaining_truncated_code = &remaining_code[next_newline_index..];
+        let comment = &code[0..(next_newline_index + word.len())];
+        let span = SourceSpan::new(code_index, code_index + comment.len());
+        let token = Token::Documentation(span, comment);
+        return Ok((token, remaining_truncated_code, span.end as usize, None));
+    }
+
     // Tokenize comments
-    if word == "/" {
-        let (next_word, remaining_code, _) = search_next_word(remaining_code, new_index);
-        if next_word == "/" {
-            let next_newline_index = remaining_code
-                .chars()
-                .position(|c| c == '\n')
-                .unwrap_or(remaining_code.len());
-            let remaining_truncated_code = &remaining_code[next_newline_index..];
-            let comment = &code[0..(next_newline_index + 2)];
-            let span = SourceSpan::new(code_index, code_index + comment.len());
-            let token = Token::Comment(span, comment);
-            return Ok((token, remaining_truncated_code, span.end as usize));
-        }
+    if word == "//" {
+        let next_newline_index = remaining_code
+            .find('\n')
+            .unwrap_or(remaining_code.len());
+        let remaining_truncated_code = &remaining_code[next_newline_index..];
+        let comment = &code[0..(next_newline_index + word.len())];
+        let span = SourceSpan::new(code_index, code_index + comment.len());
+        let token = Token::Comment(span, comment);
+        return Ok((token, remaining_truncated_code, span.end as usize, None));
     }
 
     // Tokenize reserved words
@@ -131,6 +357,18 @@ pub fn tokenize_next_word(code: &str, code_index: usize) -> Result<(Token, &str,
         "-" => Token::Minus(span, word),
         "*" => Token::Star(span, word),
         "/" => Token::Slash(span, word),
+
+        // Multi-character operators (maximal munch, see `search_next_word`)
+        "==" => Token::EqualEqual(span, word),
+        "!=" => Token::NotEqual(span, word),
+        "<=" => Token::LessEqual(span, word),
+        ">=" => Token::GreaterEqual(span, word),
+        "->" => Token::Arrow(span, word),
+        "::" => Token::ColonColon(span, word),
+        ".." => Token::DotDot(span, word),
+        "+=" => Token::PlusEqual(span, word),
+        "&&" => Token::AmpAmp(span, word),
+        "||" => Token::PipePipe(span, word),
         //
         "(" => Token::LParenthesis(span, word),
         ")" => Token::RParenthesis(span, word),
@@ -229,8 +467,70 @@ pub fn tokenize_next_word(code: &str, code_index: usize) -> Result<(Token, &str,
         (token, remaining_code)
     };
 
+    // Recover from Unicode lookalike delimiters
+    //
+    // `character_is_delimiter` treats every non-ASCII-alphanumeric
+    // character as a generic delimiter, so a pasted fullwidth
+    // semicolon, a Unicode minus sign, or a smart quote falls through
+    // every match above and stays `Token::None`. Rather than let that
+    // surface as the unhelpful "Token::None was found during lexing",
+    // consult a table of common confusables and name the ASCII
+    // character it was probably meant to be. Where the confusable maps
+    // cleanly onto a single-character token we also substitute it, so
+    // lexing can continue past the typo instead of stopping dead.
+    let mut confusable_warning = None;
+    let token = if token.is_none() && word.chars().count() == 1 {
+        let confusable = word.chars().next().unwrap();
+        match lookup_confusable(confusable) {
+            Some((ascii, name)) => {
+                let message = format!(
+                    "found `{}` (U+{:04X} {}), did you mean `{}`?",
+                    confusable, confusable as u32, name, ascii
+                );
+                match confusable_token(ascii, span, word) {
+                    Some(replacement) => {
+                        confusable_warning = Some(Diagnostic::warning(message, span));
+                        replacement
+                    }
+                    None => bail!("{}", message),
+                }
+            }
+            None => token,
+        }
+    } else {
+        token
+    };
+
     // where ...
 
+    fn tokenize_string_literal(code: &str, code_index: usize) -> Result<(Token<'_>, &str, usize)> {
+        let mut escaped = false;
+        for (byte_index, c) in code.char_indices().skip(1) {
+            if escaped {
+                ensure!(
+                    matches!(c, 'n' | 't' | '\\' | '"'),
+                    "unknown escape sequence '\\{}' in string literal",
+                    c
+                );
+                escaped = false;
+                continue;
+            }
+            match c {
+                '\\' => escaped = true,
+                '"' => {
+                    let end_byte = byte_index + c.len_utf8();
+                    let literal = &code[0..end_byte];
+                    let remaining_code = &code[end_byte..];
+                    let span = SourceSpan::new(code_index, code_index + literal.len());
+                    let token = Token::StrVal(span, literal);
+                    return Ok((token, remaining_code, span.end as usize));
+                }
+                _ => {}
+            }
+        }
+        bail!("unterminated string literal")
+    }
+
     fn truncate_leading_whitespace(code: &str, code_index: usize) -> (&str, usize) {
         let next_non_whitespace_character_index = {
             code.chars()
@@ -249,13 +549,42 @@ pub fn tokenize_next_word(code: &str, code_index: usize) -> Result<(Token, &str,
         };
         let current_character_is_delimiter = next_delimiter_character_index == 0;
         let word = if current_character_is_delimiter {
-            &code[0..1] // BUG: Won't work for multi-character delimiters like ++ --
+            // Maximal munch: try the longest known multi-char operator
+            // first, and only fall back to a single-char token when none
+            // of them match. The fallback takes a whole char rather than
+            // a byte, since a confusable delimiter (e.g. a fullwidth
+            // semicolon) may be several bytes wide.
+            match_longest_delimiter_operator(code).unwrap_or_else(|| {
+                let first_char_len = code.chars().next().map_or(0, char::len_utf8);
+                &code[0..first_char_len]
+            })
         } else {
             &code[0..next_delimiter_character_index]
         };
         (word, &code[word.len()..], code_index + word.len())
     }
 
+    /// Multi-character operators ordered longest-first so the caller can
+    /// check them in a fixed number of steps.
+    const DELIMITER_OPERATORS_LEN3: &[&str] = &["///"];
+    const DELIMITER_OPERATORS_LEN2: &[&str] = &[
+        "==", "!=", "<=", ">=", "->", "::", "..", "+=", "&&", "||", "//",
+    ];
+
+    fn match_longest_delimiter_operator(code: &str) -> Option<&str> {
+        if let Some(candidate) = code.get(0..3) {
+            if DELIMITER_OPERATORS_LEN3.contains(&candidate) {
+                return Some(candidate);
+            }
+        }
+        if let Some(candidate) = code.get(0..2) {
+            if DELIMITER_OPERATORS_LEN2.contains(&candidate) {
+                return Some(candidate);
+            }
+        }
+        None
+    }
+
     fn character_is_delimiter(c: char) -> bool {
         #[allow(clippy::match_like_matches_macro)]
         match c {
@@ -287,27 +616,190 @@ pub fn tokenize_next_word(code: &str, code_index: usize) -> Result<(Token, &str,
         }
     }
 
-    Ok((token, remaining_code, span.end as usize))
+    /// Unicode codepoints that are easily typed or pasted in place of an
+    /// ASCII delimiter (fullwidth punctuation from CJK input methods, a
+    /// Unicode minus sign copied from a math document, smart quotes from
+    /// a word processor, ...), paired with the ASCII character they are
+    /// commonly mistaken for and the codepoint's Unicode name.
+    const CONFUSABLES: &[(char, char, &str)] = &[
+        ('\u{FF1B}', ';', "FULLWIDTH SEMICOLON"),
+        ('\u{037E}', ';', "GREEK QUESTION MARK"),
+        ('\u{FF1A}', ':', "FULLWIDTH COLON"),
+        ('\u{FF0C}', ',', "FULLWIDTH COMMA"),
+        ('\u{FF0E}', '.', "FULLWIDTH FULL STOP"),
+        ('\u{2212}', '-', "MINUS SIGN"),
+        ('\u{2010}', '-', "HYPHEN"),
+        ('\u{2011}', '-', "NON-BREAKING HYPHEN"),
+        ('\u{FF08}', '(', "FULLWIDTH LEFT PARENTHESIS"),
+        ('\u{FF09}', ')', "FULLWIDTH RIGHT PARENTHESIS"),
+        ('\u{FF3B}', '[', "FULLWIDTH LEFT SQUARE BRACKET"),
+        ('\u{FF3D}', ']', "FULLWIDTH RIGHT SQUARE BRACKET"),
+        ('\u{FF5B}', '{', "FULLWIDTH LEFT CURLY BRACKET"),
+        ('\u{FF5D}', '}', "FULLWIDTH RIGHT CURLY BRACKET"),
+        ('\u{FF01}', '!', "FULLWIDTH EXCLAMATION MARK"),
+        ('\u{FF1F}', '?', "FULLWIDTH QUESTION MARK"),
+        ('\u{201C}', '"', "LEFT DOUBLE QUOTATION MARK"),
+        ('\u{201D}', '"', "RIGHT DOUBLE QUOTATION MARK"),
+    ];
+
+    /// Look up `c` in [`CONFUSABLES`], returning the ASCII character it
+    /// is commonly mistaken for and that character's Unicode name.
+    fn lookup_confusable(c: char) -> Option<(char, &'static str)> {
+        CONFUSABLES
+            .iter()
+            .find(|&&(confusable, _, _)| confusable == c)
+            .map(|&(_, ascii, name)| (ascii, name))
+    }
+
+    /// Build the token a confusable's ASCII suggestion resolves to, so
+    /// lexing can recover by substituting it for the confusable.
+    ///
+    /// Quotes intentionally return `None`: recovering a string literal
+    /// opened by a smart quote would require re-scanning for its
+    /// matching close, so those are reported but not substituted.
+    fn confusable_token<'code>(
+        ascii: char,
+        span: SourceSpan,
+        word: &'code str,
+    ) -> Option<Token<'code>> {
+        match ascii {
+            ';' => Some(Token::Semicolon(span, word)),
+            ':' => Some(Token::Colon(span, word)),
+            ',' => Some(Token::Comma(span, word)),
+            '.' => Some(Token::Dot(span, word)),
+            '-' => Some(Token::Minus(span, word)),
+            '(' => Some(Token::LParenthesis(span, word)),
+            ')' => Some(Token::RParenthesis(span, word)),
+            '[' => Some(Token::LBracket(span, word)),
+            ']' => Some(Token::RBracket(span, word)),
+            '{' => Some(Token::LBrace(span, word)),
+            '}' => Some(Token::RBrace(span, word)),
+            '!' => Some(Token::Exclamation(span, word)),
+            '?' => Some(Token::Question(span, word)),
+            _ => None,
+        }
+    }
+
+    Ok((token, remaining_code, span.end as usize, confusable_warning))
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    /// Tokenize code that is expected to lex cleanly, failing the test
+    /// if any diagnostic was recorded.
+    fn tokenize_ok(code: &str) -> Result<Vec<Token<'_>>> {
+        let (tokens, diagnostics) = tokenize_string(code);
+        ensure!(
+            diagnostics.is_empty(),
+            "unexpected diagnostics: {}",
+            diagnostics.render(code)
+        );
+        Ok(tokens)
+    }
+
     #[test]
     fn test_tokenize_string() -> Result<()> {
-        let _a = tokenize_string("fn main() { }")?;
-        let _b = tokenize_string("fn main() int { return 0; }")?;
-        let _c = tokenize_string("fn main() int { let x = 0; return x; }")?;
-        let _d = tokenize_string("fn main() int { let x:int = 0; return x; }")?;
-        let _e = tokenize_string("fn main() int {\n 0\n}")?;
-        let _f = tokenize_string("fn main() int {\n let x = 0\n x\n}")?;
-        let _g = tokenize_string("fn main() int {\n let x:int = 0\n x\n}")?;
-        let _h = tokenize_string("/// this is a documentation comment\n fn main() {}")?;
-        let _i = tokenize_string("// this is a comment\n fn main() {}")?;
-        let _j = tokenize_string("// this is a comment /// with a nested comment\n fn main() {}")?;
-        let _k = tokenize_string("0 _ _0 0_ 000_000_000")?;
-        let _l = tokenize_string("123_456_789")?;
+        let _a = tokenize_ok("fn main() { }")?;
+        let _b = tokenize_ok("fn main() int { return 0; }")?;
+        let _c = tokenize_ok("fn main() int { let x = 0; return x; }")?;
+        let _d = tokenize_ok("fn main() int { let x:int = 0; return x; }")?;
+        let _e = tokenize_ok("fn main() int {\n 0\n}")?;
+        let _f = tokenize_ok("fn main() int {\n let x = 0\n x\n}")?;
+        let _g = tokenize_ok("fn main() int {\n let x:int = 0\n x\n}")?;
+        let _h = tokenize_ok("/// this is a documentation comment\n fn main() {}")?;
+        let _i = tokenize_ok("// this is a comment\n fn main() {}")?;
+        let _j = tokenize_ok("// this is a comment /// with a nested comment\n fn main() {}")?;
+        let _k = tokenize_ok("0 _ _0 0_ 000_000_000")?;
+        let _l = tokenize_ok("123_456_789")?;
+        let _m = tokenize_ok("a == b != c <= d >= e -> f :: g .. h += i && j || k")?;
+        let _n = tokenize_ok("123.45 1_000.5 flt x = 0.0")?;
+        let _o = tokenize_ok(r#"let x = "hello\n\"world\"";"#)?;
         Ok(())
     }
+
+    #[test]
+    fn test_tokenize_string_recovers_from_errors() {
+        let (tokens, diagnostics) = tokenize_string("let x = 0; @ let y = 1; let z = 2;");
+        assert!(!diagnostics.is_empty());
+        assert!(!tokens.is_empty());
+    }
+
+    #[test]
+    fn test_tokenize_string_substitutes_confusable_delimiters() {
+        let (tokens, diagnostics) = tokenize_string("let x = 0\u{FF1B} let y = x \u{2212} 1;");
+        assert!(!diagnostics.is_empty());
+        let rendered = diagnostics.render("let x = 0\u{FF1B} let y = x \u{2212} 1;");
+        assert!(rendered.contains("did you mean `;`?"));
+        assert!(rendered.contains("did you mean `-`?"));
+        assert!(tokens
+            .iter()
+            .any(|token| matches!(token, Token::Semicolon(_, _))));
+        assert!(tokens
+            .iter()
+            .any(|token| matches!(token, Token::Minus(_, _))));
+    }
+
+    #[test]
+    fn test_tokenize_string_reports_unrecoverable_confusable_quote() {
+        let (_tokens, diagnostics) = tokenize_string("let x = \u{201C}hi\u{201D};");
+        assert!(diagnostics.has_errors());
+        let rendered = diagnostics.render("let x = \u{201C}hi\u{201D};");
+        assert!(rendered.contains("did you mean `\"`?"));
+    }
+
+    #[test]
+    fn test_lexer_iterates_tokens_directly() -> Result<()> {
+        let mut tokens = vec![];
+        for token in Lexer::new("let x = 0;", LexerLimits::default()) {
+            tokens.push(token?);
+        }
+        assert_eq!(tokens.len(), 5);
+        assert!(matches!(tokens[0], Token::Let(_, _)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lexer_limits_max_num_tokens() {
+        let limits = LexerLimits {
+            max_num_tokens: 2,
+            ..LexerLimits::default()
+        };
+        let mut lexer = Lexer::new("let x = 0;", limits);
+        assert!(lexer.next().unwrap().is_ok());
+        assert!(lexer.next().unwrap().is_ok());
+        assert!(lexer.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_lexer_limits_max_identifier_length() {
+        let limits = LexerLimits {
+            max_identifier_length: 2,
+            ..LexerLimits::default()
+        };
+        let mut lexer = Lexer::new("abcdef", limits);
+        assert!(lexer.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_token_cursor_peeks_without_consuming() {
+        let mut cursor = TokenCursor::new("let x = 0;", LexerLimits::default());
+        assert!(matches!(cursor.peek(), Some(Token::Let(_, _))));
+        assert!(matches!(cursor.peek(), Some(Token::Let(_, _))));
+        assert!(matches!(cursor.advance(), Some(Token::Let(_, _))));
+        assert!(matches!(cursor.peek(), Some(Token::IdxVal(_, _))));
+    }
+
+    #[test]
+    fn test_token_cursor_recovers_from_errors() {
+        let mut cursor = TokenCursor::new("let x = 0; @ let y = 1;", LexerLimits::default());
+        let mut tokens = vec![];
+        while let Some(token) = cursor.advance() {
+            tokens.push(token);
+        }
+        let diagnostics = cursor.take_diagnostics();
+        assert!(!diagnostics.is_empty());
+        assert!(!tokens.is_empty());
+    }
 }
@@ -29,6 +29,18 @@ pub enum Token<'code> {
     Minus(SourceSpan, &'code str),
     Star(SourceSpan, &'code str),
     Slash(SourceSpan, &'code str),
+
+    // Multi-character operators
+    EqualEqual(SourceSpan, &'code str),
+    NotEqual(SourceSpan, &'code str),
+    LessEqual(SourceSpan, &'code str),
+    GreaterEqual(SourceSpan, &'code str),
+    Arrow(SourceSpan, &'code str),
+    ColonColon(SourceSpan, &'code str),
+    DotDot(SourceSpan, &'code str),
+    PlusEqual(SourceSpan, &'code str),
+    AmpAmp(SourceSpan, &'code str),
+    PipePipe(SourceSpan, &'code str),
     //
     LParenthesis(SourceSpan, &'code str),
     RParenthesis(SourceSpan, &'code str),
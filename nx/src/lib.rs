@@ -1,4 +1,5 @@
 pub mod data;
+pub mod diagnostic;
 pub mod error;
 pub mod lexer;
 pub mod parser;
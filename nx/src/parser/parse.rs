@@ -1,144 +1,633 @@
-use crate::error::{ensure, Result};
+use std::cell::Cell;
+
+use crate::error::{bail, ensure, Result};
 //
 use crate::data::ast::{AstNode, AstNodeData, AstNodeType};
-use crate::lexer::token::Token;
-
-pub fn parse_token<'code>(tokens: &'code [Token<'code>]) -> Result<()> {
-    ensure!(!tokens.is_empty(), "can not parse an empty token list");
+use crate::diagnostic::{Diagnostic, Diagnostics};
+use crate::lexer::lex::TokenCursor;
+use crate::lexer::token::{SourceSpan, Token};
 
-    let mut xast: Vec<AstNode> = vec![];
+/// Parse tokens into an AST.
+///
+/// Consumes `cursor` lazily, one token of lookahead at a time, rather
+/// than requiring the whole token stream up front.
+///
+/// Never aborts on the first problem: a failed top-level item or
+/// statement is recorded as a [`Diagnostic`] and parsing resumes past
+/// it, so the caller gets both the AST that could be recovered and the
+/// full list of problems found.
+pub fn parse_token<'code>(cursor: &mut TokenCursor<'code>) -> (Vec<AstNode>, Diagnostics) {
     let mut ast: Vec<AstNode> = vec![];
-    let mut token_index = 0;
+    let mut diagnostics = Diagnostics::new();
 
-    /// Store nodes from temporary ast
-    macro_rules! store_into_ast {
-        ($xast:ident) => {
-            for &node in $xast.iter() {
-                ast.push(node);
-            }
-            xast = $xast; // so xast isn't drop'd
-            xast.clear();
-        };
+    if cursor.peek().is_none() {
+        diagnostics.push(Diagnostic::error(
+            "can not parse an empty token list",
+            SourceSpan::default(),
+        ));
+        return (ast, diagnostics);
     }
 
     // Parse tokens one by one until all tokens are processed.
     // Since top level entities such as modules, functions, structs
     // are expected to occur first in our code, they are parsed
     // using a recursive descent algorithm.
-    while token_index < tokens.len() {
-        let token = tokens.get(token_index);
+    while let Some(token) = cursor.peek() {
         match token {
-            Some(Token::Fn(_, _)) => {
-                let (new_xast, next_token_index) = parse_function(xast, tokens, token_index)?;
-                // Store ast nodes and reset state
-                store_into_ast!(new_xast);
-                // Advance token_index to process rest of the remaining tokens
-                // Warning: Without this the loop will run forever
-                token_index = next_token_index;
+            Token::Fn(span, _) => {
+                let span = *span;
+                // Parse into a scratch buffer first: if the function
+                // turns out to be malformed we discard its partial
+                // nodes rather than leaving a half-built function in
+                // the AST, while still keeping the diagnostic.
+                let mut xast: Vec<AstNode> = vec![];
+                match parse_function(&mut xast, cursor, &mut diagnostics) {
+                    Ok(()) => {
+                        ast.append(&mut xast);
+                    }
+                    Err(error) => {
+                        diagnostics.push(Diagnostic::error(error.to_string(), span));
+                        // Keep scanning for the next recognizable
+                        // top-level item (the failed parse has already
+                        // consumed at least the `fn` token).
+                        cursor.advance();
+                    }
+                }
             }
             _ => {
-                // Reset
-                xast.clear();
-                // Advance token_index to process rest of the remaining tokens
+                // Advance the cursor to process the rest of the
+                // remaining tokens.
                 // Warning: Without this the loop will run forever
-                token_index += 1;
+                cursor.advance();
             }
         }
     }
-    dbg!(&ast);
-    dbg!("--");
 
-    Ok(())
+    (ast, diagnostics)
 }
 
 fn parse_function<'code>(
-    mut ast: Vec<AstNode>,
-    tokens: &'code [Token<'code>],
-    token_index: usize,
-) -> Result<(Vec<AstNode>, usize)> {
+    ast: &mut Vec<AstNode>,
+    cursor: &mut TokenCursor<'code>,
+    diagnostics: &mut Diagnostics,
+) -> Result<()> {
     // Mark the start of function
     ast.push(AstNode::StartFunction(AstNodeData::default()));
 
     // Parse rest of the ast nodes
-    let (ast, next_token_index) = parse_visibility(ast, tokens, token_index)?; // Should we search backwards?
-    let (ast, next_token_index) = parse_identifier(ast, tokens, next_token_index)?;
-    let (ast, next_token_index) = parse_argument(ast, tokens, next_token_index)?;
-    let (ast, next_token_index) = parse_type(ast, tokens, next_token_index)?;
-    let (ast, next_token_index) = parse_block(ast, tokens, next_token_index)?;
+    parse_visibility(ast, cursor)?; // Should we search backwards?
+    parse_identifier(ast, cursor)?;
+    parse_argument(ast, cursor)?;
+    parse_type(ast, cursor)?;
+    parse_block(ast, cursor, diagnostics)?;
 
     // Mark the end of function
-    let mut ast = ast;
     ast.push(AstNode::EndFunction(AstNodeData::default()));
 
-    // where ...
-    Ok((ast, next_token_index))
+    Ok(())
 }
 
-fn parse_visibility<'code>(
-    mut ast: Vec<AstNode>,
-    _tokens: &'code [Token<'code>],
-    token_index: usize,
-) -> Result<(Vec<AstNode>, usize)> {
-    let visibility = AstNode::Invisible(AstNodeData::default());
-    let next_token_index = token_index + 1;
-    ast.push(visibility);
-    Ok((ast, next_token_index))
+fn parse_visibility<'code>(ast: &mut Vec<AstNode>, cursor: &mut TokenCursor<'code>) -> Result<()> {
+    ast.push(AstNode::Invisible(AstNodeData::default()));
+    cursor.advance();
+    Ok(())
 }
 
-fn parse_identifier<'code>(
-    mut ast: Vec<AstNode>,
-    tokens: &'code [Token<'code>],
-    token_index: usize,
-) -> Result<(Vec<AstNode>, usize)> {
-    let identifier_token = tokens.get(token_index).unwrap();
-    let next_token_index = token_index + 1;
+fn parse_identifier<'code>(ast: &mut Vec<AstNode>, cursor: &mut TokenCursor<'code>) -> Result<()> {
+    let Some(identifier_token) = cursor.advance() else {
+        bail!("expected an identifier");
+    };
+    ensure!(identifier_token.is_idx_val(), "expected an identifier");
     let span = identifier_token.into_idx_val().unwrap().0;
-    let identifier = AstNode::Identifier(AstNodeData {
+    ast.push(AstNode::Identifier(AstNodeData {
         span,
         type_: AstNodeType::default(),
-    });
-    ast.push(identifier);
-    Ok((ast, next_token_index))
+    }));
+    Ok(())
 }
 
-fn parse_argument<'code>(
-    mut ast: Vec<AstNode>,
-    _tokens: &'code [Token<'code>],
-    token_index: usize,
-) -> Result<(Vec<AstNode>, usize)> {
+// TODO: these still just skip over the argument list / return type
+// without building any AST for them; only enough to land on the
+// function's opening '{' for `parse_block`.
+fn parse_argument<'code>(ast: &mut Vec<AstNode>, cursor: &mut TokenCursor<'code>) -> Result<()> {
+    ensure!(
+        matches!(cursor.peek(), Some(Token::LParenthesis(_, _))),
+        "expected '(' to start a function argument list"
+    );
+    cursor.advance();
+    while !matches!(cursor.peek(), Some(Token::RParenthesis(_, _)) | None) {
+        cursor.advance();
+    }
+    ensure!(
+        matches!(cursor.peek(), Some(Token::RParenthesis(_, _))),
+        "expected ')' to end a function argument list"
+    );
+    cursor.advance();
+
     ast.push(AstNode::None);
-    Ok((ast, token_index))
+    Ok(())
 }
 
-fn parse_type<'code>(
-    mut ast: Vec<AstNode>,
-    _tokens: &'code [Token<'code>],
-    token_index: usize,
-) -> Result<(Vec<AstNode>, usize)> {
+fn parse_type<'code>(ast: &mut Vec<AstNode>, cursor: &mut TokenCursor<'code>) -> Result<()> {
+    let is_type = matches!(
+        cursor.peek(),
+        Some(
+            Token::Unit(_, _)
+                | Token::Usize(_, _)
+                | Token::Int(_, _)
+                | Token::Flt(_, _)
+                | Token::Str(_, _)
+                | Token::I8(_, _)
+                | Token::U8(_, _)
+                | Token::I16(_, _)
+                | Token::U16(_, _)
+                | Token::I32(_, _)
+                | Token::U32(_, _)
+                | Token::I64(_, _)
+                | Token::U64(_, _)
+                | Token::F32(_, _)
+                | Token::F64(_, _),
+        )
+    );
+    if is_type {
+        cursor.advance();
+    }
+
     ast.push(AstNode::None);
-    Ok((ast, token_index))
+    Ok(())
 }
 
+/// Parse a function block `{ ... }` as a sequence of statements.
+///
+/// A statement that fails to parse is recorded as a diagnostic rather
+/// than aborting the whole block: parsing recovers by skipping to the
+/// next `;` (or the block's closing `}`) and continues with the
+/// statement after it.
 fn parse_block<'code>(
-    mut ast: Vec<AstNode>,
-    _tokens: &'code [Token<'code>],
-    token_index: usize,
-) -> Result<(Vec<AstNode>, usize)> {
-    ast.push(AstNode::None);
-    Ok((ast, token_index))
+    ast: &mut Vec<AstNode>,
+    cursor: &mut TokenCursor<'code>,
+    diagnostics: &mut Diagnostics,
+) -> Result<()> {
+    ensure!(
+        matches!(cursor.peek(), Some(Token::LBrace(_, _))),
+        "expected '{{' to start a function block"
+    );
+    cursor.advance();
+
+    loop {
+        match cursor.peek() {
+            Some(Token::RBrace(_, _)) => {
+                cursor.advance();
+                break;
+            }
+            Some(_) => {
+                // Parse into a scratch buffer first, the same way
+                // `parse_function` does: if the statement fails
+                // partway through, we discard whatever partial nodes
+                // it already pushed (e.g. an orphaned `StartStatement`
+                // with no matching `EndStatement`) instead of leaving
+                // them in `ast`.
+                let mut xast: Vec<AstNode> = vec![];
+                // Tracks the token the failing statement was actually
+                // looking at when it gave up, since a `Result` alone
+                // can't carry that back out.
+                let failed_at = Cell::new(token_span(cursor.peek()));
+                match parse_statement(&mut xast, cursor, &failed_at) {
+                    Ok(()) => {
+                        ast.append(&mut xast);
+                    }
+                    Err(error) => {
+                        diagnostics.push(Diagnostic::error(error.to_string(), failed_at.get()));
+                        recover_to_statement_boundary(cursor);
+                    }
+                }
+            }
+            None => bail!("expected '}}' to close a function block"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Skip forward past the next `;`, or up to (but not past) the next
+/// `}`, so the enclosing block's statement loop can resume.
+fn recover_to_statement_boundary(cursor: &mut TokenCursor) {
+    loop {
+        match cursor.peek() {
+            Some(Token::Semicolon(_, _)) => {
+                cursor.advance();
+                return;
+            }
+            Some(Token::RBrace(_, _)) | None => return,
+            Some(_) => {
+                cursor.advance();
+            }
+        }
+    }
+}
+
+/// Parse a single statement: a `let`/`var` binding, a `return`, or a
+/// bare expression, followed by an optional `;`. No terminator is
+/// required, so a statement ends wherever its expression does.
+///
+/// `failed_at` records the span of the token a nested parse was
+/// looking at right before it gave up, so the caller can point a
+/// diagnostic at the actual offending token instead of the start of
+/// this statement.
+fn parse_statement<'code>(
+    ast: &mut Vec<AstNode>,
+    cursor: &mut TokenCursor<'code>,
+    failed_at: &Cell<SourceSpan>,
+) -> Result<()> {
+    ast.push(AstNode::StartStatement(AstNodeData::default()));
+
+    match cursor.peek() {
+        Some(Token::Let(_, _)) | Some(Token::Var(_, _)) => parse_binding(ast, cursor, failed_at)?,
+        Some(Token::Return(_, _)) => parse_return(ast, cursor, failed_at)?,
+        _ => parse_expression(ast, cursor, 0, failed_at)?,
+    };
+
+    if matches!(cursor.peek(), Some(Token::Semicolon(_, _))) {
+        cursor.advance();
+    }
+
+    ast.push(AstNode::EndStatement(AstNodeData::default()));
+    Ok(())
+}
+
+fn parse_binding<'code>(
+    ast: &mut Vec<AstNode>,
+    cursor: &mut TokenCursor<'code>,
+    failed_at: &Cell<SourceSpan>,
+) -> Result<()> {
+    // `let` / `var` keyword already confirmed present by the caller.
+    cursor.advance();
+
+    parse_identifier(ast, cursor)?;
+
+    // Optional `: <type>` annotation; type checking happens later, so
+    // for now we only need to skip past it to reach the initializer.
+    if matches!(cursor.peek(), Some(Token::Colon(_, _))) {
+        cursor.advance();
+        parse_type(ast, cursor)?;
+    }
+
+    failed_at.set(token_span(cursor.peek()));
+    ensure!(
+        matches!(cursor.peek(), Some(Token::Equal(_, _))),
+        "expected '=' in let/var binding"
+    );
+    cursor.advance();
+
+    parse_expression(ast, cursor, 0, failed_at)
+}
+
+fn parse_return<'code>(
+    ast: &mut Vec<AstNode>,
+    cursor: &mut TokenCursor<'code>,
+    failed_at: &Cell<SourceSpan>,
+) -> Result<()> {
+    // `return` keyword already confirmed present by the caller.
+    cursor.advance();
+    parse_expression(ast, cursor, 0, failed_at)
+}
+
+/// Parse an expression using precedence climbing (a Pratt parser).
+///
+/// Wraps the whole expression in the existing `StartExpression`/
+/// `EndExpression` markers, then defers to [`parse_expression_bp`] for
+/// the recursive precedence-climbing core.
+fn parse_expression<'code>(
+    ast: &mut Vec<AstNode>,
+    cursor: &mut TokenCursor<'code>,
+    min_bp: u8,
+    failed_at: &Cell<SourceSpan>,
+) -> Result<()> {
+    ast.push(AstNode::StartExpression(AstNodeData::default()));
+    parse_expression_bp(ast, cursor, min_bp, failed_at)?;
+    ast.push(AstNode::EndExpression(AstNodeData::default()));
+    Ok(())
+}
+
+/// Parse a prefix atom, then repeatedly fold in infix operators whose
+/// left binding power is `>= min_bp`, recursing on the right-hand side
+/// with the operator's right binding power. Nodes are emitted in
+/// postfix order (operand(s), then the operator), so the flat `ast`
+/// Vec doubles as a stack-machine encoding of the expression tree.
+fn parse_expression_bp<'code>(
+    ast: &mut Vec<AstNode>,
+    cursor: &mut TokenCursor<'code>,
+    min_bp: u8,
+    failed_at: &Cell<SourceSpan>,
+) -> Result<()> {
+    parse_expression_atom(ast, cursor, failed_at)?;
+
+    while let Some(operator) = cursor.peek() {
+        let Some((left_bp, right_bp)) = infix_binding_power(operator) else {
+            break;
+        };
+        if left_bp < min_bp {
+            break;
+        }
+        let operator_span = operator_token_span(operator);
+        cursor.advance();
+
+        parse_expression_bp(ast, cursor, right_bp, failed_at)?;
+
+        ast.push(AstNode::BinaryOp(AstNodeData {
+            span: operator_span,
+            type_: AstNodeType::default(),
+        }));
+    }
+
+    Ok(())
+}
+
+fn parse_expression_atom<'code>(
+    ast: &mut Vec<AstNode>,
+    cursor: &mut TokenCursor<'code>,
+    failed_at: &Cell<SourceSpan>,
+) -> Result<()> {
+    failed_at.set(token_span(cursor.peek()));
+    match cursor.peek() {
+        Some(token @ (Token::Minus(span, _) | Token::Exclamation(span, _))) => {
+            let operator_span = *span;
+            let right_bp = prefix_binding_power(token).unwrap();
+            cursor.advance();
+            parse_expression_bp(ast, cursor, right_bp, failed_at)?;
+            ast.push(AstNode::UnaryOp(AstNodeData {
+                span: operator_span,
+                type_: AstNodeType::default(),
+            }));
+            Ok(())
+        }
+        Some(Token::LParenthesis(_, _)) => {
+            cursor.advance();
+            parse_expression_bp(ast, cursor, 0, failed_at)?;
+            failed_at.set(token_span(cursor.peek()));
+            ensure!(
+                matches!(cursor.peek(), Some(Token::RParenthesis(_, _))),
+                "expected closing ')' in expression"
+            );
+            cursor.advance();
+            Ok(())
+        }
+        Some(Token::IntVal(span, _)) => {
+            let span = *span;
+            cursor.advance();
+            ast.push(AstNode::Integer(AstNodeData {
+                span,
+                type_: AstNodeType::default(),
+            }));
+            Ok(())
+        }
+        Some(Token::FltVal(span, _)) => {
+            let span = *span;
+            cursor.advance();
+            ast.push(AstNode::Float(AstNodeData {
+                span,
+                type_: AstNodeType::default(),
+            }));
+            Ok(())
+        }
+        Some(Token::StrVal(span, _)) => {
+            let span = *span;
+            cursor.advance();
+            ast.push(AstNode::String(AstNodeData {
+                span,
+                type_: AstNodeType::default(),
+            }));
+            Ok(())
+        }
+        Some(Token::IdxVal(span, _)) => {
+            let span = *span;
+            cursor.advance();
+            ast.push(AstNode::Identifier(AstNodeData {
+                span,
+                type_: AstNodeType::default(),
+            }));
+            Ok(())
+        }
+        _ => bail!("expected an expression"),
+    }
+}
+
+/// Binding powers for infix operators: `(left_bp, right_bp)`.
+///
+/// `*`/`/` bind tighter than `+`/`-`, which bind tighter than
+/// comparisons. `=` is right-associative (its right binding power is
+/// lower than its left), everything else is left-associative (right
+/// binding power is left binding power + 1).
+fn infix_binding_power(token: &Token) -> Option<(u8, u8)> {
+    match token {
+        Token::Equal(_, _) => Some((2, 1)),
+        Token::LAngle(_, _)
+        | Token::RAngle(_, _)
+        | Token::EqualEqual(_, _)
+        | Token::NotEqual(_, _)
+        | Token::LessEqual(_, _)
+        | Token::GreaterEqual(_, _) => Some((3, 4)),
+        Token::Plus(_, _) | Token::Minus(_, _) => Some((5, 6)),
+        Token::Star(_, _) | Token::Slash(_, _) => Some((7, 8)),
+        _ => None,
+    }
+}
+
+/// Binding power for a prefix (unary) operator's operand, higher than
+/// every infix operator so `-a * b` parses as `(-a) * b`.
+fn prefix_binding_power(token: &Token) -> Option<u8> {
+    match token {
+        Token::Minus(_, _) | Token::Exclamation(_, _) => Some(9),
+        _ => None,
+    }
+}
+
+fn operator_token_span(token: &Token) -> SourceSpan {
+    match token {
+        Token::Equal(span, _)
+        | Token::Plus(span, _)
+        | Token::Minus(span, _)
+        | Token::Star(span, _)
+        | Token::Slash(span, _)
+        | Token::LAngle(span, _)
+        | Token::RAngle(span, _)
+        | Token::EqualEqual(span, _)
+        | Token::NotEqual(span, _)
+        | Token::LessEqual(span, _)
+        | Token::GreaterEqual(span, _) => *span,
+        _ => unreachable!("operator_token_span called on a non-operator token"),
+    }
+}
+
+/// Extract the `SourceSpan` carried by any token, for pointing a
+/// diagnostic at the token a failed parse stopped on.
+fn token_span(token: Option<&Token>) -> SourceSpan {
+    macro_rules! span_of {
+        ($token:expr, $( $variant:ident ),+ $(,)?) => {
+            match $token {
+                $( Token::$variant(span, _) => Some(*span), )+
+                Token::None => None,
+            }
+        };
+    }
+
+    token
+        .and_then(|token| {
+            span_of!(
+                token,
+                Documentation,
+                Comment,
+                Semicolon,
+                Colon,
+                Comma,
+                Dot,
+                Equal,
+                Plus,
+                Minus,
+                Star,
+                Slash,
+                EqualEqual,
+                NotEqual,
+                LessEqual,
+                GreaterEqual,
+                Arrow,
+                ColonColon,
+                DotDot,
+                PlusEqual,
+                AmpAmp,
+                PipePipe,
+                LParenthesis,
+                RParenthesis,
+                LBracket,
+                RBracket,
+                LAngle,
+                RAngle,
+                LBrace,
+                RBrace,
+                Exclamation,
+                Question,
+                Dollar,
+                Hash,
+                Use,
+                Let,
+                Var,
+                As,
+                In,
+                Return,
+                Break,
+                Continue,
+                Macro,
+                Module,
+                Fn,
+                Struct,
+                Enum,
+                Instance,
+                Implement,
+                Match,
+                If,
+                Else,
+                For,
+                While,
+                Loop,
+                Unit,
+                Usize,
+                Int,
+                Flt,
+                Str,
+                I8,
+                U8,
+                I16,
+                U16,
+                I32,
+                U32,
+                I64,
+                U64,
+                F32,
+                F64,
+                IntVal,
+                FltVal,
+                StrVal,
+                IdxVal,
+            )
+        })
+        .unwrap_or_default()
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::lexer::lex::tokenize_string;
+    use crate::lexer::lex::LexerLimits;
+
+    /// Tokenize and parse code that is expected to be clean, failing
+    /// the test if any diagnostic was recorded at either stage.
+    fn parse_ok(code: &str) -> Result<Vec<AstNode>> {
+        let mut cursor = TokenCursor::new(code, LexerLimits::default());
+        let (ast, parse_diagnostics) = parse_token(&mut cursor);
+        let lex_diagnostics = cursor.take_diagnostics();
+        ensure!(
+            lex_diagnostics.is_empty(),
+            "unexpected lexer diagnostics: {}",
+            lex_diagnostics.render(code)
+        );
+        ensure!(
+            parse_diagnostics.is_empty(),
+            "unexpected parser diagnostics: {}",
+            parse_diagnostics.render(code)
+        );
+        Ok(ast)
+    }
 
     #[test]
     fn test_parse_token() -> Result<()> {
-        let xa = tokenize_string("fn main() { }")?;
-        let xb = tokenize_string("/// Returns zero\n fn zero() int { let x = 0; return x; }")?;
-        let _a = parse_token(&xa)?;
-        let _b = parse_token(&xb)?;
+        let _a = parse_ok("fn main() { }")?;
+        let _b = parse_ok("/// Returns zero\n fn zero() int { let x = 0; return x; }")?;
+        let _c = parse_ok("fn main() int { let x = 1 + 2 * 3; var y:int = -x; return x = y; }")?;
         Ok(())
     }
+
+    #[test]
+    fn test_parse_token_recovers_from_errors() {
+        let mut cursor = TokenCursor::new(
+            "fn main() int { let x = ; return x; }",
+            LexerLimits::default(),
+        );
+        let (ast, diagnostics) = parse_token(&mut cursor);
+        assert!(!diagnostics.is_empty());
+        assert!(!ast.is_empty());
+    }
+
+    #[test]
+    fn test_parse_block_diagnostic_points_at_failing_token_not_statement_start() {
+        // The binding's `=` is never followed by a value, so the
+        // failure is at the `;`, not at `let` where the statement
+        // started.
+        let code = "fn main() int { let x = ; return x; }";
+        let mut cursor = TokenCursor::new(code, LexerLimits::default());
+        let (_, diagnostics) = parse_token(&mut cursor);
+        let semicolon_column = code.find(';').unwrap() + 1;
+        let rendered = diagnostics.render(code);
+        assert!(rendered.starts_with(&format!("1:{semicolon_column}: ")));
+    }
+
+    #[test]
+    fn test_parse_block_discards_partial_nodes_from_a_failed_statement() {
+        // The failing `let x = ;` statement must not leave an orphaned
+        // `StartStatement` with no matching `EndStatement` behind: the
+        // only `StartStatement`/`EndStatement` pair in the AST should
+        // be the one from the `return x;` statement that follows it.
+        let code = "fn main() int { let x = ; return x; }";
+        let mut cursor = TokenCursor::new(code, LexerLimits::default());
+        let (ast, diagnostics) = parse_token(&mut cursor);
+        assert!(!diagnostics.is_empty());
+        let start_statements = ast
+            .iter()
+            .filter(|node| matches!(node, AstNode::StartStatement(_)))
+            .count();
+        let end_statements = ast
+            .iter()
+            .filter(|node| matches!(node, AstNode::EndStatement(_)))
+            .count();
+        assert_eq!(start_statements, end_statements);
+        assert_eq!(start_statements, 1);
+    }
 }